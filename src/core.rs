@@ -1 +1,6 @@
-pub mod arithmetic;
\ No newline at end of file
+pub mod affine;
+pub mod arithmetic;
+pub mod finite_difference;
+pub mod linalg;
+pub mod measured;
+pub mod quaternion;
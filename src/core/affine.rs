@@ -0,0 +1,476 @@
+use std::{
+  ops::{Add, Div, Mul, Neg, Sub},
+  sync::atomic::{AtomicUsize, Ordering},
+};
+
+use super::arithmetic::{One, Zero};
+
+/// Source of fresh noise symbols, shared by all affine forms so that independently created forms
+/// never accidentally share a symbol.
+static NEXT_NOISE_SYMBOL: AtomicUsize = AtomicUsize::new(0);
+
+fn fresh_noise_symbol() -> usize {
+  return NEXT_NOISE_SYMBOL.fetch_add(1, Ordering::Relaxed);
+}
+
+/// An affine form `x0 + x1 e1 + ... + xn en` enclosing an uncertain real quantity, where each
+/// noise symbol `ei` ranges independently over `[-1, 1]`.
+///
+/// Unlike intervals, affine forms remember which values derive from the same source of
+/// uncertainty, so correlated terms cancel: for `x` in `[1, 3]`, `x - x` is exactly zero and
+/// `x * (4 - x)` encloses `[3, 5]` rather than `[1, 9]`. Linear operations introduce no error
+/// beyond rounding; nonlinear ones add a fresh noise symbol bounding the approximation error.
+///
+/// Results are guaranteed enclosures despite `f64` rounding and underflow: every rounding error
+/// is bounded and folded into a fresh noise symbol, and each form also carries an outward-rounded
+/// interval hull, which `to_interval` intersects with the range of the affine form. The hull
+/// keeps enclosures that no affine form with `f64` coefficients can represent, such as a strictly
+/// positive `[1e-300, 1]`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Affine {
+  center: f64,
+  /// Coefficients of the noise symbols, sorted by symbol and without zero coefficients.
+  terms: Vec<(usize, f64)>,
+  /// An interval known to contain the value, tracked by interval arithmetic.
+  hull: (f64, f64),
+}
+
+impl Affine {
+  /// The exactly known value `value`.
+  pub fn constant(value: f64) -> Affine {
+    return Affine {
+      center: value,
+      terms: Vec::new(),
+      hull: (value, value),
+    };
+  }
+
+  /// An uncertain value known to lie in `[lo, hi]`, independent of all existing affine forms.
+  pub fn from_interval(lo: f64, hi: f64) -> Affine {
+    assert!(lo <= hi, "empty interval [{}, {}]", lo, hi);
+    let center = lo * 0.5 + hi * 0.5;
+    let radius = add_up(hi, -center).max(add_up(center, -lo));
+    return Affine {
+      center,
+      terms: Vec::new(),
+      hull: (lo, hi),
+    }
+    .with_fresh_term(radius);
+  }
+
+  /// The central value of the form.
+  pub fn center(&self) -> f64 {
+    return self.center;
+  }
+
+  /// An upper bound on the deviation of the affine form from its center.
+  pub fn radius(&self) -> f64 {
+    return self.terms.iter().fold(0.0, |r, &(_, c)| add_up(r, c.abs()));
+  }
+
+  /// An interval `(lo, hi)` enclosing the value.
+  pub fn to_interval(&self) -> (f64, f64) {
+    let radius = self.radius();
+    let (lo, hi) = self.hull;
+    return (
+      add_down(self.center, -radius).max(lo),
+      add_up(self.center, radius).min(hi),
+    );
+  }
+
+  /// The reciprocal of the form, using the min-range linear approximation of `1/t`. When the
+  /// slope of that approximation is out of `f64` range, the result is instead a fresh form
+  /// spanning the reciprocal of the range, uncorrelated with `self`.
+  ///
+  /// Panics if the form's range contains zero, since no affine form encloses the result.
+  pub fn recip(&self) -> Affine {
+    let (lo, hi) = self.to_interval();
+    if hi < 0.0 {
+      return -(-self.clone()).recip();
+    }
+    assert!(lo > 0.0, "reciprocal of an affine form containing zero");
+    // Write 1/t = slope * t + g(t) with slope close to -1/hi^2. Any slope gives a valid
+    // enclosure: g is convex on [lo, hi], so its maximum is at an endpoint, and by the AM-GM
+    // inequality g(t) = 1/t + |slope| t >= 2 sqrt(|slope|).
+    let slope = -1.0 / (hi * hi);
+    let g_up = |t: f64| add_up(div_up(1.0, t), mul_up(-slope, t));
+    let max = g_up(lo).max(g_up(hi));
+    let min = 2.0 * (-slope).sqrt().next_down();
+    let offset = min * 0.5 + max * 0.5;
+    let deviation = add_up(max, -offset).max(add_up(offset, -min));
+
+    let (terms, terms_error) = combine_terms(self, slope, &Affine::zero(), 0.0);
+    let (product, product_error) = two_product(slope, self.center);
+    let (center, center_error) = two_sum(product, offset);
+    let product_error = product_error_bound(slope, self.center, product, product_error);
+    let error = add_up(add_up(terms_error, product_error), center_error.abs());
+    let (hull_lo, hull_hi) = (div_down(1.0, hi), div_up(1.0, lo));
+    let reciprocal = Affine {
+      center,
+      terms,
+      hull: (hull_lo, hull_hi),
+    }
+    .with_fresh_term(add_up(deviation, error));
+    if !(reciprocal.center.is_finite() && reciprocal.radius().is_finite()) {
+      return Affine::from_interval(hull_lo, hull_hi);
+    }
+    return reciprocal;
+  }
+
+  fn with_fresh_term(mut self, coefficient: f64) -> Affine {
+    if coefficient != 0.0 {
+      self.terms.push((fresh_noise_symbol(), coefficient.abs()));
+    }
+    return self;
+  }
+}
+
+/// `p + q` computed exactly as the rounded sum plus a signed error (Knuth's TwoSum).
+fn two_sum(p: f64, q: f64) -> (f64, f64) {
+  let sum = p + q;
+  let q_part = sum - p;
+  let p_part = sum - q_part;
+  return (sum, (p - p_part) + (q - q_part));
+}
+
+/// The smallest positive `f64`, a subnormal.
+const SMALLEST_SUBNORMAL: f64 = f64::from_bits(1);
+
+/// Products of at least this magnitude have a rounding error that is itself exactly
+/// representable; below it, the error may underflow.
+const EXACT_PRODUCT_ERROR_THRESHOLD: f64 = 2.0 * f64::MIN_POSITIVE / f64::EPSILON;
+
+/// `p * q` computed as the rounded product plus a signed error, using a fused multiply-add. The
+/// error is exact unless the product is tiny, in which case it is itself rounded and may be off
+/// by up to `underflow_slack(p, q, product)`.
+fn two_product(p: f64, q: f64) -> (f64, f64) {
+  let product = p * q;
+  return (product, p.mul_add(q, -product));
+}
+
+/// A bound on how far the error computed by `two_product(p, q)` may be from the exact error.
+fn underflow_slack(p: f64, q: f64, product: f64) -> f64 {
+  if p != 0.0 && q != 0.0 && product.abs() < EXACT_PRODUCT_ERROR_THRESHOLD {
+    return SMALLEST_SUBNORMAL;
+  }
+  return 0.0;
+}
+
+/// An upper bound on `|p * q - product|`, given `(product, error) = two_product(p, q)`.
+fn product_error_bound(p: f64, q: f64, product: f64, error: f64) -> f64 {
+  return add_up(error.abs(), underflow_slack(p, q, product));
+}
+
+/// Steps the rounded result `value` of an operation outward by one ulp, in the direction of
+/// `error`, when the exact result differs from it by `error`.
+fn round_up(value: f64, error: f64) -> f64 {
+  return if error > 0.0 { value.next_up() } else { value };
+}
+
+fn round_down(value: f64, error: f64) -> f64 {
+  return if error < 0.0 {
+    value.next_down()
+  } else {
+    value
+  };
+}
+
+fn add_up(p: f64, q: f64) -> f64 {
+  let (sum, error) = two_sum(p, q);
+  return round_up(sum, error);
+}
+
+fn add_down(p: f64, q: f64) -> f64 {
+  let (sum, error) = two_sum(p, q);
+  return round_down(sum, error);
+}
+
+fn mul_up(p: f64, q: f64) -> f64 {
+  let (product, error) = two_product(p, q);
+  return round_up(product, error + underflow_slack(p, q, product));
+}
+
+fn mul_down(p: f64, q: f64) -> f64 {
+  let (product, error) = two_product(p, q);
+  return round_down(product, error - underflow_slack(p, q, product));
+}
+
+/// The sign of `p / q - quotient`, given the exact residual `p - quotient * q`.
+fn quotient_error(quotient: f64, p: f64, q: f64) -> f64 {
+  return (-quotient).mul_add(q, p) * q.signum();
+}
+
+fn div_up(p: f64, q: f64) -> f64 {
+  let quotient = p / q;
+  return round_up(quotient, quotient_error(quotient, p, q));
+}
+
+fn div_down(p: f64, q: f64) -> f64 {
+  let quotient = p / q;
+  return round_down(quotient, quotient_error(quotient, p, q));
+}
+
+/// The noise coefficients of `a * x + b * y` and an upper bound on their total rounding error.
+fn combine_terms(x: &Affine, a: f64, y: &Affine, b: f64) -> (Vec<(usize, f64)>, f64) {
+  let mut terms = Vec::with_capacity(x.terms.len() + y.terms.len());
+  let mut error = 0.0;
+  let (mut i, mut j) = (0, 0);
+  while i < x.terms.len() || j < y.terms.len() {
+    let (symbol, c, d) = match (x.terms.get(i), y.terms.get(j)) {
+      (Some(&(s, c)), Some(&(t, _))) if s < t => {
+        i += 1;
+        (s, c, 0.0)
+      }
+      (Some(&(s, _)), Some(&(t, d))) if t < s => {
+        j += 1;
+        (t, 0.0, d)
+      }
+      (Some(&(s, c)), Some(&(_, d))) => {
+        i += 1;
+        j += 1;
+        (s, c, d)
+      }
+      (Some(&(s, c)), None) => {
+        i += 1;
+        (s, c, 0.0)
+      }
+      (None, Some(&(t, d))) => {
+        j += 1;
+        (t, 0.0, d)
+      }
+      (None, None) => unreachable!(),
+    };
+    let (p, p_error) = two_product(a, c);
+    let (q, q_error) = two_product(b, d);
+    let (coefficient, sum_error) = two_sum(p, q);
+    let products_error = add_up(
+      product_error_bound(a, c, p, p_error),
+      product_error_bound(b, d, q, q_error),
+    );
+    error = add_up(error, add_up(products_error, sum_error.abs()));
+    if coefficient != 0.0 {
+      terms.push((symbol, coefficient));
+    }
+  }
+  return (terms, error);
+}
+
+impl Zero for Affine {
+  fn zero() -> Affine {
+    return Affine::constant(0.0);
+  }
+}
+
+impl One for Affine {
+  fn one() -> Affine {
+    return Affine::constant(1.0);
+  }
+}
+
+impl Add for Affine {
+  type Output = Affine;
+
+  fn add(self, rhs: Affine) -> Affine {
+    let ((lo, hi), (rhs_lo, rhs_hi)) = (self.to_interval(), rhs.to_interval());
+    let (terms, terms_error) = combine_terms(&self, 1.0, &rhs, 1.0);
+    let (center, center_error) = two_sum(self.center, rhs.center);
+    return Affine {
+      center,
+      terms,
+      hull: (add_down(lo, rhs_lo), add_up(hi, rhs_hi)),
+    }
+    .with_fresh_term(add_up(terms_error, center_error.abs()));
+  }
+}
+
+impl Sub for Affine {
+  type Output = Affine;
+
+  fn sub(self, rhs: Affine) -> Affine {
+    return self + -rhs;
+  }
+}
+
+impl Mul for Affine {
+  type Output = Affine;
+
+  fn mul(self, rhs: Affine) -> Affine {
+    let ((lo, hi), (rhs_lo, rhs_hi)) = (self.to_interval(), rhs.to_interval());
+    let corners = [(lo, rhs_lo), (lo, rhs_hi), (hi, rhs_lo), (hi, rhs_hi)];
+    let hull_lo = corners
+      .iter()
+      .map(|&(p, q)| mul_down(p, q))
+      .fold(f64::INFINITY, f64::min);
+    let hull_hi = corners
+      .iter()
+      .map(|&(p, q)| mul_up(p, q))
+      .fold(f64::NEG_INFINITY, f64::max);
+
+    // The product of the linear parts is kept; the quadratic remainder is bounded by the product
+    // of the radii.
+    let (terms, terms_error) = combine_terms(&self, rhs.center, &rhs, self.center);
+    let (center, center_error) = two_product(self.center, rhs.center);
+    let center_error = product_error_bound(self.center, rhs.center, center, center_error);
+    let error = add_up(
+      mul_up(self.radius(), rhs.radius()),
+      add_up(terms_error, center_error),
+    );
+    return Affine {
+      center,
+      terms,
+      hull: (hull_lo, hull_hi),
+    }
+    .with_fresh_term(error);
+  }
+}
+
+impl Div for Affine {
+  type Output = Affine;
+
+  /// Panics if the range of `rhs` contains zero.
+  #[allow(clippy::suspicious_arithmetic_impl)]
+  fn div(self, rhs: Affine) -> Affine {
+    return self * rhs.recip();
+  }
+}
+
+impl Neg for Affine {
+  type Output = Affine;
+
+  fn neg(self) -> Affine {
+    let (lo, hi) = self.hull;
+    return Affine {
+      center: -self.center,
+      terms: self.terms.iter().map(|&(s, c)| (s, -c)).collect(),
+      hull: (-hi, -lo),
+    };
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::Affine;
+  use crate::core::arithmetic::{One, Zero};
+
+  fn assert_encloses(form: &Affine, values: impl Iterator<Item = f64>) {
+    let (lo, hi) = form.to_interval();
+    for value in values {
+      assert!(
+        lo <= value && value <= hi,
+        "{} not in [{}, {}]",
+        value,
+        lo,
+        hi
+      );
+    }
+  }
+
+  fn samples(lo: f64, hi: f64) -> impl Iterator<Item = f64> {
+    return (0..=100).map(move |i| lo + (hi - lo) * (i as f64) / 100.0);
+  }
+
+  #[test]
+  fn test_zero_and_one_are_identities() {
+    let x = Affine::from_interval(-1.0, 3.0);
+    assert_eq!(x.clone() + Affine::zero(), x);
+    assert_eq!(x.clone() - Affine::zero(), x);
+    assert_eq!((x.clone() * Affine::one()).to_interval(), x.to_interval());
+    assert_eq!(Affine::zero() * x, Affine::zero());
+  }
+
+  #[test]
+  fn test_correlated_terms_cancel() {
+    let x = Affine::from_interval(1.0, 3.0);
+    assert_eq!((x.clone() - x.clone()).to_interval(), (0.0, 0.0));
+    assert_eq!(
+      (x.clone() + x.clone() - x.clone() * Affine::constant(2.0)).radius(),
+      0.0
+    );
+    let y = Affine::from_interval(1.0, 3.0);
+    assert_eq!((x - y).to_interval(), (-2.0, 2.0));
+  }
+
+  #[test]
+  fn test_product_is_tighter_than_interval_arithmetic() {
+    let x = Affine::from_interval(1.0, 3.0);
+    let product = x.clone() * (Affine::constant(4.0) - x);
+    assert_encloses(&product, samples(1.0, 3.0).map(|t| t * (4.0 - t)));
+    // The true range is [3, 4]; interval arithmetic gives [1, 9].
+    assert_eq!(product.to_interval(), (3.0, 5.0));
+  }
+
+  #[test]
+  fn test_quotient_encloses_true_range() {
+    let x = Affine::from_interval(1.0, 2.0);
+    let y = Affine::from_interval(-4.0, -2.0);
+    let quotient = x.clone() / y;
+    let (lo, hi) = quotient.to_interval();
+    assert!(lo <= -1.0 && hi >= -0.25, "[{}, {}]", lo, hi);
+    let reciprocal = Affine::one() / x;
+    assert_encloses(&reciprocal, samples(1.0, 2.0).map(|t| 1.0 / t));
+  }
+
+  #[test]
+  fn test_from_interval_encloses_inexact_endpoints() {
+    for &(lo, hi) in &[
+      (0.1, 0.3),
+      (-0.7, 0.2),
+      (1e-300, 1.0),
+      (1.0 / 3.0, 2.0 / 3.0),
+    ] {
+      let (enclosure_lo, enclosure_hi) = Affine::from_interval(lo, hi).to_interval();
+      assert!(
+        enclosure_lo <= lo && hi <= enclosure_hi,
+        "[{}, {}] not in [{}, {}]",
+        lo,
+        hi,
+        enclosure_lo,
+        enclosure_hi
+      );
+    }
+  }
+
+  #[test]
+  fn test_rounding_errors_widen_enclosures() {
+    // The exact sum of the doubles nearest 0.1 and 0.2 lies strictly between the doubles 0.3 and
+    // 0.30000000000000004.
+    let (lo, hi) = (Affine::constant(0.1) + Affine::constant(0.2)).to_interval();
+    assert!(lo <= 0.3 && 0.30000000000000004 <= hi, "[{}, {}]", lo, hi);
+  }
+
+  #[test]
+  fn test_reciprocal_of_tiny_positive_range() {
+    let (lo, hi) = Affine::from_interval(1e-300, 1.0).recip().to_interval();
+    assert!(lo <= 1.0 && 1e300 <= hi, "[{}, {}]", lo, hi);
+    assert!(lo > 0.0, "[{}, {}]", lo, hi);
+  }
+
+  #[test]
+  fn test_underflowing_products_are_enclosed() {
+    let tiny = Affine::constant(1e-200);
+    let (_, hi) = (tiny.clone() * tiny).to_interval();
+    assert!(hi > 0.0, "1e-200 * 1e-200 <= {}", hi);
+
+    let x = Affine::from_interval(-1e-200, 1e-200);
+    let (_, hi) = (x.clone() * x).to_interval();
+    assert!(hi > 0.0, "x * x <= {}", hi);
+
+    let y = Affine::constant(1e-170) * Affine::from_interval(1.0, 2.0) * Affine::constant(1e-170);
+    let (_, hi) = y.to_interval();
+    assert!(hi > 0.0, "1e-170 * [1, 2] * 1e-170 <= {}", hi);
+  }
+
+  #[test]
+  fn test_reciprocal_with_out_of_range_slope() {
+    let reciprocal = Affine::from_interval(1e-200, 2e-200).recip();
+    assert!(reciprocal.center().is_finite() && reciprocal.radius().is_finite());
+    assert_encloses(&reciprocal, [5e199, 1e200].into_iter());
+    assert_eq!((reciprocal.clone() - reciprocal).to_interval(), (0.0, 0.0));
+  }
+
+  #[test]
+  #[should_panic(expected = "containing zero")]
+  fn test_division_by_form_containing_zero_panics() {
+    let _ = Affine::one() / Affine::from_interval(-1.0, 1.0);
+  }
+}
@@ -32,6 +32,45 @@ impl One for f64 {
   }
 }
 
+#[cfg(feature = "decimal")]
+impl Zero for rust_decimal::Decimal {
+  fn zero() -> rust_decimal::Decimal {
+    return rust_decimal::Decimal::ZERO;
+  }
+}
+#[cfg(feature = "decimal")]
+impl One for rust_decimal::Decimal {
+  fn one() -> rust_decimal::Decimal {
+    return rust_decimal::Decimal::ONE;
+  }
+}
+
+#[cfg(feature = "half")]
+impl Zero for half::f16 {
+  fn zero() -> half::f16 {
+    return half::f16::ZERO;
+  }
+}
+#[cfg(feature = "half")]
+impl One for half::f16 {
+  fn one() -> half::f16 {
+    return half::f16::ONE;
+  }
+}
+
+#[cfg(feature = "half")]
+impl Zero for half::bf16 {
+  fn zero() -> half::bf16 {
+    return half::bf16::ZERO;
+  }
+}
+#[cfg(feature = "half")]
+impl One for half::bf16 {
+  fn one() -> half::bf16 {
+    return half::bf16::ONE;
+  }
+}
+
 #[cfg(test)]
 mod tests {
   use std::{
@@ -53,6 +92,12 @@ mod tests {
   }
   impl Arithmetic for f32 {}
   impl Arithmetic for f64 {}
+  #[cfg(feature = "decimal")]
+  impl Arithmetic for rust_decimal::Decimal {}
+  #[cfg(feature = "half")]
+  impl Arithmetic for half::f16 {}
+  #[cfg(feature = "half")]
+  impl Arithmetic for half::bf16 {}
 
   fn assert_additive_identity<T: Arithmetic<T> + PartialEq<T> + Copy + Debug>(ts: &[T]) {
     for &t in ts {
@@ -97,4 +142,70 @@ mod tests {
   fn test_f64_one_is_multiplicative_identity() {
     assert_multiplicative_identity::<f64>(F64_TEST_VALUES);
   }
+
+  #[cfg(feature = "decimal")]
+  fn decimal_test_values() -> Vec<rust_decimal::Decimal> {
+    use rust_decimal::Decimal;
+    return vec![
+      Decimal::NEGATIVE_ONE,
+      Decimal::ZERO,
+      Decimal::ONE,
+      Decimal::new(314159, 5),
+      Decimal::MAX,
+    ];
+  }
+
+  #[cfg(feature = "decimal")]
+  #[test]
+  fn test_decimal_zero_is_additive_identity() {
+    assert_additive_identity::<rust_decimal::Decimal>(&decimal_test_values());
+  }
+
+  #[cfg(feature = "decimal")]
+  #[test]
+  fn test_decimal_one_is_multiplicative_identity() {
+    assert_multiplicative_identity::<rust_decimal::Decimal>(&decimal_test_values());
+  }
+
+  #[cfg(feature = "half")]
+  static F16_TEST_VALUES: &[half::f16] = &[
+    half::f16::NEG_ONE,
+    half::f16::ZERO,
+    half::f16::ONE,
+    half::f16::PI,
+    half::f16::E,
+  ];
+
+  #[cfg(feature = "half")]
+  #[test]
+  fn test_f16_zero_is_additive_identity() {
+    assert_additive_identity::<half::f16>(F16_TEST_VALUES);
+  }
+
+  #[cfg(feature = "half")]
+  #[test]
+  fn test_f16_one_is_multiplicative_identity() {
+    assert_multiplicative_identity::<half::f16>(F16_TEST_VALUES);
+  }
+
+  #[cfg(feature = "half")]
+  static BF16_TEST_VALUES: &[half::bf16] = &[
+    half::bf16::NEG_ONE,
+    half::bf16::ZERO,
+    half::bf16::ONE,
+    half::bf16::PI,
+    half::bf16::E,
+  ];
+
+  #[cfg(feature = "half")]
+  #[test]
+  fn test_bf16_zero_is_additive_identity() {
+    assert_additive_identity::<half::bf16>(BF16_TEST_VALUES);
+  }
+
+  #[cfg(feature = "half")]
+  #[test]
+  fn test_bf16_one_is_multiplicative_identity() {
+    assert_multiplicative_identity::<half::bf16>(BF16_TEST_VALUES);
+  }
 }
@@ -0,0 +1,287 @@
+/// Options controlling the finite-difference approximation computed by `fd_gradient`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct FdOptions {
+  /// The largest step tried for each coordinate `x_i`, raised to `cbrt(EPSILON) * |x_i|` when that
+  /// is larger so that steps stay well above the spacing of floats near `x_i`. Must be positive.
+  pub initial_step: f64,
+  /// The factor by which the step shrinks between successive extrapolation levels. Must be
+  /// greater than one.
+  pub step_ratio: f64,
+  /// The maximum number of step sizes tried per coordinate. Must be at least one; with a single
+  /// level the result is a plain central difference and its error estimate is infinite. A partial
+  /// that has not converged after this many levels is also reported with an infinite error.
+  pub max_levels: usize,
+}
+
+impl Default for FdOptions {
+  fn default() -> FdOptions {
+    return FdOptions {
+      initial_step: 0.1,
+      step_ratio: 1.4,
+      max_levels: 30,
+    };
+  }
+}
+
+/// A finite-difference gradient together with an estimate of the absolute error of each partial.
+/// An error of `f64::INFINITY` means the estimate did not converge and should not be trusted.
+#[derive(Clone, Debug, PartialEq)]
+pub struct FdGradient {
+  pub gradient: Vec<f64>,
+  pub errors: Vec<f64>,
+}
+
+/// An estimate has converged once its error estimate is within this fraction of the estimate
+/// itself. Until then the step keeps shrinking, since large steps may straddle a pole or leave the
+/// function's domain; partials that are zero up to round-off therefore rarely converge.
+const CONVERGED_RELATIVE_ERROR: f64 = 1e-8;
+
+/// Once the error of the highest-order estimate exceeds the best error seen so far by this
+/// factor, round-off has taken over and smaller steps are not tried.
+const DIVERGENCE_FACTOR: f64 = 2.0;
+
+/// Approximates the gradient of `f` at `x` using central differences refined by Richardson
+/// extrapolation. The step size is chosen adaptively per coordinate by keeping the extrapolated
+/// estimate with the smallest error estimate (Ridders' method). Callers should check `errors`
+/// before trusting the result: a partial that did not converge within `max_levels` steps has an
+/// infinite error.
+pub fn fd_gradient<F: Fn(&[f64]) -> f64>(f: F, x: &[f64], opts: FdOptions) -> FdGradient {
+  assert!(
+    opts.initial_step > 0.0,
+    "initial step {} is not positive",
+    opts.initial_step
+  );
+  assert!(
+    opts.step_ratio > 1.0,
+    "step ratio {} does not shrink the step",
+    opts.step_ratio
+  );
+  assert!(opts.max_levels >= 1, "max_levels must be at least one");
+  let mut point = x.to_vec();
+  let (gradient, errors) = (0..x.len())
+    .map(|i| partial_derivative(&f, &mut point, i, &opts))
+    .unzip();
+  return FdGradient { gradient, errors };
+}
+
+/// The central-difference quotient of `f` along coordinate `i` at `point` with step `h`. Divides
+/// by the distance between the points actually evaluated, so that rounding `x_i ± h` does not
+/// skew the quotient. Leaves `point` unchanged.
+fn central_difference<F: Fn(&[f64]) -> f64>(f: &F, point: &mut [f64], i: usize, h: f64) -> f64 {
+  let x_i = point[i];
+  let (forward_x, backward_x) = (x_i + h, x_i - h);
+  point[i] = forward_x;
+  let forward = f(point);
+  point[i] = backward_x;
+  let backward = f(point);
+  point[i] = x_i;
+  return (forward - backward) / (forward_x - backward_x);
+}
+
+/// The estimate of the `i`th partial derivative and its error estimate.
+fn partial_derivative<F: Fn(&[f64]) -> f64>(
+  f: &F,
+  point: &mut [f64],
+  i: usize,
+  opts: &FdOptions,
+) -> (f64, f64) {
+  let ratio_squared = opts.step_ratio * opts.step_ratio;
+  let mut h = opts.initial_step.max(f64::EPSILON.cbrt() * point[i].abs());
+
+  // `previous[j]` and `current[j]` hold the estimates extrapolated `j` times for the previous and
+  // current step size, respectively. A non-finite difference (e.g. from stepping outside the
+  // function's domain) restarts the extrapolation from the next step.
+  let mut previous = vec![central_difference(f, point, i, h)];
+  let mut best = previous[0];
+  let mut best_error = f64::INFINITY;
+  let mut converged = false;
+  for _ in 1..opts.max_levels {
+    h /= opts.step_ratio;
+    let mut current = vec![central_difference(f, point, i, h)];
+    if current[0].is_finite() && previous[0].is_finite() {
+      let mut factor = ratio_squared;
+      for j in 1..=previous.len() {
+        let extrapolated = (current[j - 1] * factor - previous[j - 1]) / (factor - 1.0);
+        factor *= ratio_squared;
+        let error = (extrapolated - current[j - 1])
+          .abs()
+          .max((extrapolated - previous[j - 1]).abs());
+        if error <= best_error {
+          best_error = error;
+          best = extrapolated;
+        }
+        current.push(extrapolated);
+      }
+      converged = best_error <= CONVERGED_RELATIVE_ERROR * best.abs();
+      let diverging = (current[current.len() - 1] - previous[previous.len() - 1]).abs()
+        >= DIVERGENCE_FACTOR * best_error;
+      if converged && diverging {
+        break;
+      }
+    }
+    previous = current;
+  }
+  if !converged {
+    return (best, f64::INFINITY);
+  }
+  // Agreement between extrapolations can be exact, but the estimate itself is still rounded.
+  return (best, best_error.max(f64::EPSILON * best.abs()));
+}
+
+#[cfg(test)]
+mod tests {
+  use super::{fd_gradient, FdGradient, FdOptions};
+
+  fn assert_close(actual: &FdGradient, expected: &[f64], tolerance: f64) {
+    assert_eq!(actual.gradient.len(), expected.len());
+    for (a, e) in actual.gradient.iter().zip(expected) {
+      assert!(
+        (a - e).abs() <= tolerance * e.abs().max(1.0),
+        "{:?} != {:?}",
+        actual,
+        expected
+      );
+    }
+  }
+
+  fn assert_error_estimates_cover(actual: &FdGradient, expected: &[f64]) {
+    for ((a, e), error) in actual.gradient.iter().zip(expected).zip(&actual.errors) {
+      assert!(
+        (a - e).abs() <= 10.0 * error,
+        "{:?} != {:?}",
+        actual,
+        expected
+      );
+    }
+  }
+
+  #[test]
+  fn test_fd_gradient_of_polynomial_and_trig() {
+    let f = |v: &[f64]| v[0] * v[0] * v[1] + v[1].sin();
+    let (x, y) = (1.5, -0.5);
+    let gradient = fd_gradient(f, &[x, y], FdOptions::default());
+    let expected = [2.0 * x * y, x * x + y.cos()];
+    assert_close(&gradient, &expected, 1e-10);
+    assert_error_estimates_cover(&gradient, &expected);
+  }
+
+  #[test]
+  fn test_fd_gradient_at_large_magnitude() {
+    let f = |v: &[f64]| (v[0] / 1000.0).exp();
+    let x = 2000.0;
+    let gradient = fd_gradient(f, &[x], FdOptions::default());
+    assert_close(&gradient, &[(x / 1000.0).exp() / 1000.0], 1e-10);
+  }
+
+  #[test]
+  fn test_fd_gradient_keeps_step_above_float_spacing() {
+    // The default initial step is far below the spacing of floats near 1e20.
+    let gradient = fd_gradient(|v: &[f64]| v[0] * v[0], &[1e20], FdOptions::default());
+    assert_close(&gradient, &[2e20], 1e-10);
+    assert_error_estimates_cover(&gradient, &[2e20]);
+  }
+
+  #[test]
+  fn test_fd_gradient_of_oscillating_function_at_large_magnitude() {
+    let x: f64 = 1e6;
+    let gradient = fd_gradient(|v: &[f64]| v[0].sin(), &[x], FdOptions::default());
+    assert_close(&gradient, &[x.cos()], 1e-8);
+    assert_error_estimates_cover(&gradient, &[x.cos()]);
+  }
+
+  #[test]
+  fn test_fd_gradient_near_pole() {
+    let gradient = fd_gradient(|v: &[f64]| 1.0 / v[0], &[0.05], FdOptions::default());
+    assert_close(&gradient, &[-400.0], 1e-8);
+    assert_error_estimates_cover(&gradient, &[-400.0]);
+  }
+
+  #[test]
+  fn test_fd_gradient_reports_failure_to_converge_close_to_pole() {
+    // Every step but the last few straddles the pole at zero.
+    let gradient = fd_gradient(|v: &[f64]| 1.0 / v[0], &[1e-5], FdOptions::default());
+    assert_eq!(gradient.errors, vec![f64::INFINITY]);
+    assert_error_estimates_cover(&gradient, &[-1e10]);
+  }
+
+  #[test]
+  fn test_fd_gradient_close_to_pole_with_more_levels() {
+    let opts = FdOptions {
+      max_levels: 60,
+      ..FdOptions::default()
+    };
+    let gradient = fd_gradient(|v: &[f64]| 1.0 / v[0], &[1e-5], opts);
+    assert_close(&gradient, &[-1e10], 1e-8);
+    assert_error_estimates_cover(&gradient, &[-1e10]);
+  }
+
+  #[test]
+  fn test_fd_gradient_of_rapidly_varying_function() {
+    let gradient = fd_gradient(
+      |v: &[f64]| (100.0 * v[0]).sin(),
+      &[0.0],
+      FdOptions::default(),
+    );
+    assert_close(&gradient, &[100.0], 1e-8);
+    assert_error_estimates_cover(&gradient, &[100.0]);
+  }
+
+  #[test]
+  fn test_fd_gradient_near_domain_boundary() {
+    // Large steps evaluate sqrt at negative arguments.
+    let gradient = fd_gradient(|v: &[f64]| v[0].sqrt(), &[0.01], FdOptions::default());
+    assert_close(&gradient, &[5.0], 1e-8);
+    assert_error_estimates_cover(&gradient, &[5.0]);
+  }
+
+  #[test]
+  fn test_fd_gradient_with_single_level_is_central_difference() {
+    let f = |v: &[f64]| v[0] * v[0] * v[0];
+    let opts = FdOptions {
+      initial_step: 0.5,
+      max_levels: 1,
+      ..FdOptions::default()
+    };
+    // The central difference of x^3 with step h is 3x^2 + h^2.
+    let gradient = fd_gradient(f, &[1.0], opts);
+    assert_close(&gradient, &[3.25], 1e-12);
+    assert_eq!(gradient.errors, vec![f64::INFINITY]);
+  }
+
+  #[test]
+  fn test_fd_gradient_of_empty_point() {
+    let gradient = fd_gradient(|_: &[f64]| 1.0, &[], FdOptions::default());
+    assert!(gradient.gradient.is_empty());
+    assert!(gradient.errors.is_empty());
+  }
+
+  #[test]
+  #[should_panic(expected = "not positive")]
+  fn test_fd_gradient_rejects_zero_step() {
+    let opts = FdOptions {
+      initial_step: 0.0,
+      ..FdOptions::default()
+    };
+    fd_gradient(|v: &[f64]| v[0], &[1.0], opts);
+  }
+
+  #[test]
+  #[should_panic(expected = "does not shrink")]
+  fn test_fd_gradient_rejects_non_shrinking_ratio() {
+    let opts = FdOptions {
+      step_ratio: 1.0,
+      ..FdOptions::default()
+    };
+    fd_gradient(|v: &[f64]| v[0], &[1.0], opts);
+  }
+
+  #[test]
+  #[should_panic(expected = "at least one")]
+  fn test_fd_gradient_rejects_zero_levels() {
+    let opts = FdOptions {
+      max_levels: 0,
+      ..FdOptions::default()
+    };
+    fd_gradient(|v: &[f64]| v[0], &[1.0], opts);
+  }
+}
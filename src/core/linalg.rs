@@ -0,0 +1,389 @@
+use std::ops::{Add, Div, Mul, Neg, Sub};
+
+use super::arithmetic::{One, Zero};
+
+/// Chooses the pivot row during elimination.
+pub trait PivotStrategy<T> {
+  /// The index into `column` of the entry to pivot on, or `None` if every entry is zero.
+  fn select(column: &[T]) -> Option<usize>;
+}
+
+/// Pivots on the first nonzero entry. Appropriate for exact types, where any nonzero pivot is as
+/// good as another, including ones without an ordering such as finite fields.
+pub struct FirstNonZero;
+
+impl<T: Zero + PartialEq> PivotStrategy<T> for FirstNonZero {
+  fn select(column: &[T]) -> Option<usize> {
+    return column.iter().position(|t| *t != T::zero());
+  }
+}
+
+/// Pivots on the entry of largest magnitude (partial pivoting). Appropriate for floating-point
+/// types, where small pivots amplify rounding errors. Only an exactly zero column counts as having
+/// no pivot, so with rounding a singular matrix usually yields tiny pivots rather than `None`.
+pub struct LargestMagnitude;
+
+impl<T: Copy + Zero + PartialOrd + Neg<Output = T>> PivotStrategy<T> for LargestMagnitude {
+  fn select(column: &[T]) -> Option<usize> {
+    let abs = |t: T| if t < T::zero() { -t } else { t };
+    let mut best: Option<(usize, T)> = None;
+    for (i, &t) in column.iter().enumerate() {
+      let magnitude = abs(t);
+      if magnitude > best.map_or(T::zero(), |(_, m)| m) {
+        best = Some((i, magnitude));
+      }
+    }
+    return best.map(|(i, _)| i);
+  }
+}
+
+/// The decomposition `PA = LU` of a square matrix `A`, where `P` is a permutation matrix, `L` is
+/// unit lower triangular and `U` is upper triangular.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Lu<T> {
+  /// `L` below the diagonal and `U` on and above it.
+  factors: Vec<Vec<T>>,
+  /// Row `i` of `PA` is row `permutation[i]` of `A`.
+  permutation: Vec<usize>,
+  /// Whether `P` is an odd permutation.
+  odd: bool,
+}
+
+/// Computes the LU decomposition of the square matrix `a`, given as a slice of rows, pivoting
+/// with `P`. Returns `None` if elimination meets a column with no nonzero pivot.
+///
+/// For exact types this happens precisely when `a` is singular. For floating-point types rounding
+/// rarely leaves a pivot exactly zero, so a singular or nearly singular `a` typically yields
+/// `Some` with diagonal entries of `U` that are tiny relative to the entries of `a`; callers must
+/// check those pivots (e.g. via `upper()` or `determinant()`) before trusting `solve`.
+pub fn lu<T, P>(a: &[Vec<T>]) -> Option<Lu<T>>
+where
+  T: Copy + Sub<T, Output = T> + Mul<T, Output = T> + Div<T, Output = T>,
+  P: PivotStrategy<T>,
+{
+  let n = a.len();
+  assert!(a.iter().all(|row| row.len() == n), "matrix is not square");
+  let mut factors = a.to_vec();
+  let mut permutation: Vec<usize> = (0..n).collect();
+  let mut odd = false;
+  for k in 0..n {
+    let column: Vec<T> = factors[k..].iter().map(|row| row[k]).collect();
+    let pivot = k + P::select(&column)?;
+    if pivot != k {
+      factors.swap(k, pivot);
+      permutation.swap(k, pivot);
+      odd = !odd;
+    }
+    let (eliminated, remaining) = factors.split_at_mut(k + 1);
+    let pivot_row = &eliminated[k];
+    for row in remaining {
+      let multiplier = row[k] / pivot_row[k];
+      row[k] = multiplier;
+      for (t, &p) in row[(k + 1)..].iter_mut().zip(&pivot_row[(k + 1)..]) {
+        *t = *t - multiplier * p;
+      }
+    }
+  }
+  return Some(Lu {
+    factors,
+    permutation,
+    odd,
+  });
+}
+
+impl<T: Copy + Zero + One> Lu<T> {
+  /// The unit lower triangular factor `L`.
+  pub fn lower(&self) -> Vec<Vec<T>> {
+    return self.triangle(|i, j| match i.cmp(&j) {
+      std::cmp::Ordering::Greater => Some(self.factors[i][j]),
+      std::cmp::Ordering::Equal => Some(T::one()),
+      std::cmp::Ordering::Less => None,
+    });
+  }
+
+  /// The upper triangular factor `U`.
+  pub fn upper(&self) -> Vec<Vec<T>> {
+    return self.triangle(|i, j| (i <= j).then(|| self.factors[i][j]));
+  }
+
+  fn triangle<F: Fn(usize, usize) -> Option<T>>(&self, entry: F) -> Vec<Vec<T>> {
+    let n = self.factors.len();
+    return (0..n)
+      .map(|i| (0..n).map(|j| entry(i, j).unwrap_or(T::zero())).collect())
+      .collect();
+  }
+}
+
+impl<T> Lu<T> {
+  /// The row permutation: row `i` of `PA` is row `permutation()[i]` of `A`.
+  pub fn permutation(&self) -> &[usize] {
+    return &self.permutation;
+  }
+}
+
+impl<T> Lu<T>
+where
+  T: Copy + One + Sub<T, Output = T> + Mul<T, Output = T> + Div<T, Output = T> + Neg<Output = T>,
+{
+  /// The determinant of `A`.
+  pub fn determinant(&self) -> T {
+    let product = (0..self.factors.len()).fold(T::one(), |d, i| d * self.factors[i][i]);
+    return if self.odd { -product } else { product };
+  }
+
+  /// The solution `x` of `Ax = b`.
+  pub fn solve(&self, b: &[T]) -> Vec<T> {
+    let n = self.factors.len();
+    assert_eq!(b.len(), n, "right-hand side has the wrong length");
+    // Forward substitution with L, then back substitution with U.
+    let mut x: Vec<T> = self.permutation.iter().map(|&i| b[i]).collect();
+    for i in 0..n {
+      for j in 0..i {
+        x[i] = x[i] - self.factors[i][j] * x[j];
+      }
+    }
+    for i in (0..n).rev() {
+      for j in (i + 1)..n {
+        x[i] = x[i] - self.factors[i][j] * x[j];
+      }
+      x[i] = x[i] / self.factors[i][i];
+    }
+    return x;
+  }
+}
+
+/// The decomposition `A = QR` of an `m x n` matrix `A` with `m >= n` and linearly independent
+/// columns, where `Q` is `m x n` with mutually orthogonal columns and `R` is `n x n` unit upper
+/// triangular.
+///
+/// The columns of `Q` are not normalized, since that would need square roots, which a general
+/// field lacks. Scaling column `j` of `Q` by `1/|q_j|` and row `j` of `R` by `|q_j|` gives the
+/// orthonormal decomposition.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Qr<T> {
+  pub q: Vec<Vec<T>>,
+  pub r: Vec<Vec<T>>,
+}
+
+/// Computes the QR decomposition of `a`, given as a slice of rows, by modified Gram-Schmidt.
+/// Returns `None` if a column of `Q` has an inner product with itself of exactly zero.
+///
+/// For exact ordered types this happens precisely when the columns of `a` are linearly dependent;
+/// over unordered fields it can also happen for independent columns. For floating-point types
+/// rounding rarely leaves an exact zero, so dependent columns typically yield `Some` with a
+/// column of `Q` that is tiny relative to the columns of `a`, and a meaningless `R`.
+pub fn qr<T>(a: &[Vec<T>]) -> Option<Qr<T>>
+where
+  T: Copy
+    + Zero
+    + One
+    + PartialEq
+    + Add<T, Output = T>
+    + Sub<T, Output = T>
+    + Mul<T, Output = T>
+    + Div<T, Output = T>,
+{
+  let m = a.len();
+  let n = a.first().map_or(0, |row| row.len());
+  assert!(
+    a.iter().all(|row| row.len() == n),
+    "matrix rows differ in length"
+  );
+  assert!(m >= n, "matrix has more columns than rows");
+  let dot = |u: &[T], v: &[T]| (0..m).fold(T::zero(), |s, i| s + u[i] * v[i]);
+
+  let mut columns: Vec<Vec<T>> = (0..n)
+    .map(|j| a.iter().map(|row| row[j]).collect())
+    .collect();
+  let mut r = vec![vec![T::zero(); n]; n];
+  for j in 0..n {
+    let norm_squared = dot(&columns[j], &columns[j]);
+    if norm_squared == T::zero() {
+      return None;
+    }
+    r[j][j] = T::one();
+    let (orthogonalized, remaining) = columns.split_at_mut(j + 1);
+    let q_j = &orthogonalized[j];
+    for (k, column) in remaining.iter_mut().enumerate() {
+      let coefficient = dot(q_j, column) / norm_squared;
+      r[j][j + 1 + k] = coefficient;
+      for (t, &q) in column.iter_mut().zip(q_j) {
+        *t = *t - coefficient * q;
+      }
+    }
+  }
+  let q = (0..m)
+    .map(|i| columns.iter().map(|column| column[i]).collect())
+    .collect();
+  return Some(Qr { q, r });
+}
+
+#[cfg(test)]
+mod tests {
+  use std::ops::{Add, Div, Mul, Neg, Sub};
+
+  use super::{lu, qr, FirstNonZero, LargestMagnitude};
+  use crate::core::arithmetic::{One, Zero};
+
+  /// The integers modulo 7, an exact field with no ordering.
+  #[derive(Clone, Copy, Debug, PartialEq)]
+  struct Mod7(u8);
+
+  impl Zero for Mod7 {
+    fn zero() -> Mod7 {
+      return Mod7(0);
+    }
+  }
+  impl One for Mod7 {
+    fn one() -> Mod7 {
+      return Mod7(1);
+    }
+  }
+  impl Add for Mod7 {
+    type Output = Mod7;
+    fn add(self, rhs: Mod7) -> Mod7 {
+      return Mod7((self.0 + rhs.0) % 7);
+    }
+  }
+  impl Sub for Mod7 {
+    type Output = Mod7;
+    fn sub(self, rhs: Mod7) -> Mod7 {
+      return Mod7((self.0 + 7 - rhs.0) % 7);
+    }
+  }
+  impl Mul for Mod7 {
+    type Output = Mod7;
+    fn mul(self, rhs: Mod7) -> Mod7 {
+      return Mod7((self.0 * rhs.0) % 7);
+    }
+  }
+  impl Div for Mod7 {
+    type Output = Mod7;
+    #[allow(clippy::suspicious_arithmetic_impl)]
+    fn div(self, rhs: Mod7) -> Mod7 {
+      // By Fermat's little theorem, rhs^-1 = rhs^5.
+      let inverse = (0..5).fold(Mod7::one(), |p, _| p * rhs);
+      return self * inverse;
+    }
+  }
+  impl Neg for Mod7 {
+    type Output = Mod7;
+    fn neg(self) -> Mod7 {
+      return Mod7::zero() - self;
+    }
+  }
+
+  fn matrix<T: Copy, U: Copy>(rows: &[&[U]], f: fn(U) -> T) -> Vec<Vec<T>> {
+    return rows
+      .iter()
+      .map(|row| row.iter().map(|&u| f(u)).collect())
+      .collect();
+  }
+
+  fn product<T: Copy + Zero + Add<T, Output = T> + Mul<T, Output = T>>(
+    a: &[Vec<T>],
+    b: &[Vec<T>],
+  ) -> Vec<Vec<T>> {
+    return a
+      .iter()
+      .map(|row| {
+        (0..b[0].len())
+          .map(|j| (0..b.len()).fold(T::zero(), |s, k| s + row[k] * b[k][j]))
+          .collect()
+      })
+      .collect();
+  }
+
+  #[test]
+  fn test_lu_with_partial_pivoting() {
+    let a = matrix(
+      &[&[1.0, 2.0, 3.0], &[4.0, 8.0, 6.0], &[2.0, 1.0, 1.0]],
+      |x| x,
+    );
+    let decomposition = lu::<f64, LargestMagnitude>(&a).unwrap();
+    assert_eq!(decomposition.permutation(), &[1, 2, 0]);
+    let permuted: Vec<Vec<f64>> = decomposition
+      .permutation()
+      .iter()
+      .map(|&i| a[i].clone())
+      .collect();
+    assert_eq!(
+      product(&decomposition.lower(), &decomposition.upper()),
+      permuted
+    );
+    assert_eq!(decomposition.determinant(), -18.0);
+    assert_eq!(decomposition.solve(&[6.0, 18.0, 4.0]), vec![1.0, 1.0, 1.0]);
+  }
+
+  #[test]
+  fn test_lu_over_finite_field() {
+    let a = matrix(&[&[0, 3, 1], &[2, 5, 4], &[1, 1, 6]], Mod7);
+    let decomposition = lu::<Mod7, FirstNonZero>(&a).unwrap();
+    assert_eq!(decomposition.permutation()[0], 1);
+    let permuted: Vec<Vec<Mod7>> = decomposition
+      .permutation()
+      .iter()
+      .map(|&i| a[i].clone())
+      .collect();
+    assert_eq!(
+      product(&decomposition.lower(), &decomposition.upper()),
+      permuted
+    );
+    // det = 0(30 - 4) - 3(12 - 4) + 1(2 - 5) = -27 = 1 (mod 7).
+    assert_eq!(decomposition.determinant(), Mod7(1));
+    let b = [Mod7(4), Mod7(4), Mod7(1)];
+    let x = decomposition.solve(&b);
+    let column = |v: &[Mod7]| v.iter().map(|&t| vec![t]).collect::<Vec<_>>();
+    assert_eq!(product(&a, &column(&x)), column(&b));
+  }
+
+  #[test]
+  fn test_lu_of_singular_matrix_is_none() {
+    let a = matrix(&[&[1.0, 2.0], &[2.0, 4.0]], |x| x);
+    assert!(lu::<f64, LargestMagnitude>(&a).is_none());
+    assert!(lu::<f64, FirstNonZero>(&a).is_none());
+  }
+
+  static RANK_TWO: &[&[u8]] = &[&[1, 2, 3], &[4, 5, 6], &[7, 8, 9]];
+
+  #[test]
+  fn test_lu_detects_singularity_exactly_only_for_exact_types() {
+    assert!(lu::<Mod7, FirstNonZero>(&matrix(RANK_TWO, |u| Mod7(u % 7))).is_none());
+    // Rounding leaves a tiny nonzero last pivot instead of an exact zero.
+    let decomposition = lu::<f64, LargestMagnitude>(&matrix(RANK_TWO, f64::from)).unwrap();
+    let last_pivot = decomposition.upper()[2][2];
+    assert!(last_pivot != 0.0 && last_pivot.abs() < 1e-12 * 9.0);
+    assert!(decomposition.determinant().abs() < 1e-12);
+  }
+
+  #[test]
+  fn test_qr_has_orthogonal_q_and_unit_triangular_r() {
+    let a = matrix(&[&[1.0, 3.0], &[1.0, 1.0], &[0.0, 4.0]], |x| x);
+    let decomposition = qr(&a).unwrap();
+    assert_eq!(product(&decomposition.q, &decomposition.r), a);
+    let q_transpose: Vec<Vec<f64>> = (0..2)
+      .map(|j| decomposition.q.iter().map(|row| row[j]).collect())
+      .collect();
+    let gram = product(&q_transpose, &decomposition.q);
+    assert_eq!(gram[0][1], 0.0);
+    assert_eq!(gram[1][0], 0.0);
+    assert_eq!(decomposition.r[0][0], 1.0);
+    assert_eq!(decomposition.r[1][0], 0.0);
+    assert_eq!(decomposition.r[1][1], 1.0);
+  }
+
+  #[test]
+  fn test_qr_of_dependent_columns_is_none() {
+    let a = matrix(&[&[1.0, 2.0], &[2.0, 4.0], &[3.0, 6.0]], |x| x);
+    assert!(qr(&a).is_none());
+  }
+
+  #[test]
+  fn test_qr_detects_dependence_exactly_only_for_exact_types() {
+    assert!(qr(&matrix(RANK_TWO, |u| Mod7(u % 7))).is_none());
+    // Rounding leaves a tiny nonzero third column of Q instead of an exact zero.
+    let decomposition = qr(&matrix(RANK_TWO, f64::from)).unwrap();
+    let norm_squared: f64 = decomposition.q.iter().map(|row| row[2] * row[2]).sum();
+    assert!(norm_squared != 0.0 && norm_squared.sqrt() < 1e-12 * 9.0);
+  }
+}
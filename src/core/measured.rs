@@ -0,0 +1,196 @@
+use std::ops::{Add, Div, Mul, Neg, Sub};
+
+use super::arithmetic::{One, Zero};
+
+/// A measured quantity: a value together with its uncertainty. Arithmetic on `Measured` values
+/// propagates uncertainty to first order, i.e. the variance of `f(x, y)` is
+/// `(df/dx)^2 var(x) + (df/dy)^2 var(y)`, treating the operands as independent.
+///
+/// Because operands are assumed independent, repeated use of the same measurement overestimates
+/// the uncertainty; for example `x - x` has twice the variance of `x` rather than none.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Measured<T> {
+  value: T,
+  variance: T,
+}
+
+impl<T: Copy + Mul<T, Output = T>> Measured<T> {
+  /// A measurement of `value` with standard deviation `std_dev`.
+  pub fn new(value: T, std_dev: T) -> Measured<T> {
+    return Measured {
+      value,
+      variance: std_dev * std_dev,
+    };
+  }
+}
+
+impl<T: Copy> Measured<T> {
+  /// A measurement of `value` with the given `variance`.
+  pub fn with_variance(value: T, variance: T) -> Measured<T> {
+    return Measured { value, variance };
+  }
+
+  /// The central value of the measurement.
+  pub fn value(&self) -> T {
+    return self.value;
+  }
+
+  /// The variance of the measurement, i.e. its standard deviation squared.
+  pub fn variance(&self) -> T {
+    return self.variance;
+  }
+}
+
+impl<T: Zero> Measured<T> {
+  /// An exactly known quantity, with no uncertainty.
+  pub fn exact(value: T) -> Measured<T> {
+    return Measured {
+      value,
+      variance: T::zero(),
+    };
+  }
+}
+
+impl Measured<f32> {
+  /// The standard deviation of the measurement.
+  pub fn std_dev(&self) -> f32 {
+    return self.variance.sqrt();
+  }
+}
+
+impl Measured<f64> {
+  /// The standard deviation of the measurement.
+  pub fn std_dev(&self) -> f64 {
+    return self.variance.sqrt();
+  }
+}
+
+impl<T: Zero> Zero for Measured<T> {
+  fn zero() -> Measured<T> {
+    return Measured::exact(T::zero());
+  }
+}
+
+impl<T: Zero + One> One for Measured<T> {
+  fn one() -> Measured<T> {
+    return Measured::exact(T::one());
+  }
+}
+
+impl<T: Add<T, Output = T>> Add for Measured<T> {
+  type Output = Measured<T>;
+
+  fn add(self, rhs: Measured<T>) -> Measured<T> {
+    return Measured {
+      value: self.value + rhs.value,
+      variance: self.variance + rhs.variance,
+    };
+  }
+}
+
+impl<T: Add<T, Output = T> + Sub<T, Output = T>> Sub for Measured<T> {
+  type Output = Measured<T>;
+
+  fn sub(self, rhs: Measured<T>) -> Measured<T> {
+    return Measured {
+      value: self.value - rhs.value,
+      variance: self.variance + rhs.variance,
+    };
+  }
+}
+
+impl<T: Copy + Add<T, Output = T> + Mul<T, Output = T>> Mul for Measured<T> {
+  type Output = Measured<T>;
+
+  fn mul(self, rhs: Measured<T>) -> Measured<T> {
+    // d(xy)/dx = y, d(xy)/dy = x.
+    return Measured {
+      value: self.value * rhs.value,
+      variance: rhs.value * rhs.value * self.variance + self.value * self.value * rhs.variance,
+    };
+  }
+}
+
+impl<T: Copy + Add<T, Output = T> + Mul<T, Output = T> + Div<T, Output = T>> Div for Measured<T> {
+  type Output = Measured<T>;
+
+  fn div(self, rhs: Measured<T>) -> Measured<T> {
+    // d(x/y)/dx = 1/y, d(x/y)/dy = -(x/y)/y.
+    let value = self.value / rhs.value;
+    return Measured {
+      value,
+      variance: (self.variance + value * value * rhs.variance) / (rhs.value * rhs.value),
+    };
+  }
+}
+
+impl<T: Neg<Output = T>> Neg for Measured<T> {
+  type Output = Measured<T>;
+
+  fn neg(self) -> Measured<T> {
+    return Measured {
+      value: -self.value,
+      variance: self.variance,
+    };
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::Measured;
+  use crate::core::arithmetic::{One, Zero};
+
+  fn assert_close(actual: f64, expected: f64) {
+    assert!(
+      (actual - expected).abs() <= 1e-12 * expected.abs().max(1.0),
+      "{} != {}",
+      actual,
+      expected
+    );
+  }
+
+  static TEST_VALUES: &[(f64, f64)] = &[(-1.0, 0.5), (0.0, 0.0), (2.0, 0.25), (3.5, 1.0)];
+
+  #[test]
+  fn test_zero_and_one_are_identities() {
+    for &(value, std_dev) in TEST_VALUES {
+      let m = Measured::new(value, std_dev);
+      assert_eq!(m + Measured::zero(), m);
+      assert_eq!(m - Measured::zero(), m);
+      assert_eq!(m * Measured::one(), m);
+      assert_eq!(m / Measured::one(), m);
+    }
+  }
+
+  #[test]
+  fn test_sum_and_difference_add_variances() {
+    let x = Measured::new(2.0, 0.3);
+    let y = Measured::new(5.0, 0.4);
+    assert_close((x + y).value(), 7.0);
+    assert_close((x + y).std_dev(), 0.5);
+    assert_close((x - y).value(), -3.0);
+    assert_close((x - y).std_dev(), 0.5);
+    assert_eq!((-x).variance(), x.variance());
+  }
+
+  #[test]
+  fn test_product_and_quotient_add_relative_variances() {
+    let x = Measured::new(2.0, 0.06);
+    let y = Measured::new(4.0, 0.16);
+    // Relative uncertainties of 3% and 4% combine to 5%.
+    let product = x * y;
+    assert_close(product.value(), 8.0);
+    assert_close(product.std_dev(), 0.05 * 8.0);
+    let quotient = x / y;
+    assert_close(quotient.value(), 0.5);
+    assert_close(quotient.std_dev(), 0.05 * 0.5);
+  }
+
+  #[test]
+  fn test_exact_values_contribute_no_uncertainty() {
+    let x = Measured::new(3.0, 0.1);
+    let scaled = Measured::exact(2.0) * x;
+    assert_close(scaled.value(), 6.0);
+    assert_close(scaled.std_dev(), 0.2);
+  }
+}
@@ -0,0 +1,264 @@
+use std::ops::{Add, Div, Mul, Neg, Sub};
+
+use super::arithmetic::{One, Zero};
+
+/// The quaternion `w + x i + y j + z k`, with `i^2 = j^2 = k^2 = ijk = -1`.
+///
+/// Quaternions form a division ring rather than a field: every nonzero quaternion has an inverse,
+/// but multiplication is not commutative (`ij = k` while `ji = -k`). Accordingly `p / q` is
+/// defined as `p * q.inverse()`, which in general differs from `q.inverse() * p`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Quaternion<T> {
+  pub w: T,
+  pub x: T,
+  pub y: T,
+  pub z: T,
+}
+
+impl<T> Quaternion<T> {
+  pub fn new(w: T, x: T, y: T, z: T) -> Quaternion<T> {
+    return Quaternion { w, x, y, z };
+  }
+}
+
+impl<T: Zero> Quaternion<T> {
+  /// The quaternion with real part `w` and no imaginary part.
+  pub fn real(w: T) -> Quaternion<T> {
+    return Quaternion::new(w, T::zero(), T::zero(), T::zero());
+  }
+}
+
+impl<T: Copy + Neg<Output = T>> Quaternion<T> {
+  /// The conjugate `w - x i - y j - z k`.
+  pub fn conjugate(&self) -> Quaternion<T> {
+    return Quaternion::new(self.w, -self.x, -self.y, -self.z);
+  }
+}
+
+impl<T: Copy + Add<T, Output = T> + Mul<T, Output = T>> Quaternion<T> {
+  /// The squared norm `w^2 + x^2 + y^2 + z^2`, equal to `q * q.conjugate()`. For floats it
+  /// overflows or underflows once components exceed about `1e154` or fall below about `1e-154`.
+  pub fn norm_squared(&self) -> T {
+    return self.w * self.w + self.x * self.x + self.y * self.y + self.z * self.z;
+  }
+}
+
+impl<
+    T: Copy
+      + Zero
+      + PartialOrd
+      + Add<T, Output = T>
+      + Mul<T, Output = T>
+      + Div<T, Output = T>
+      + Neg<Output = T>,
+  > Quaternion<T>
+{
+  /// The two-sided multiplicative inverse `q.conjugate() / q.norm_squared()`. The components are
+  /// first divided by the largest of their magnitudes, so that the squared norm neither overflows
+  /// nor underflows.
+  ///
+  /// Panics if `self` is zero.
+  pub fn inverse(&self) -> Quaternion<T> {
+    let magnitude = |t: T| if t < T::zero() { -t } else { t };
+    let scale = [self.x, self.y, self.z]
+      .iter()
+      .map(|&t| magnitude(t))
+      .fold(magnitude(self.w), |m, t| if t > m { t } else { m });
+    assert!(scale != T::zero(), "inverse of the zero quaternion");
+    let scaled = Quaternion::new(
+      self.w / scale,
+      self.x / scale,
+      self.y / scale,
+      self.z / scale,
+    );
+    let norm_squared = scaled.norm_squared();
+    let conjugate = scaled.conjugate();
+    return Quaternion::new(
+      conjugate.w / norm_squared / scale,
+      conjugate.x / norm_squared / scale,
+      conjugate.y / norm_squared / scale,
+      conjugate.z / norm_squared / scale,
+    );
+  }
+}
+
+impl<T: Zero> Zero for Quaternion<T> {
+  fn zero() -> Quaternion<T> {
+    return Quaternion::real(T::zero());
+  }
+}
+
+impl<T: Zero + One> One for Quaternion<T> {
+  fn one() -> Quaternion<T> {
+    return Quaternion::real(T::one());
+  }
+}
+
+impl<T: Add<T, Output = T>> Add for Quaternion<T> {
+  type Output = Quaternion<T>;
+
+  fn add(self, rhs: Quaternion<T>) -> Quaternion<T> {
+    return Quaternion::new(
+      self.w + rhs.w,
+      self.x + rhs.x,
+      self.y + rhs.y,
+      self.z + rhs.z,
+    );
+  }
+}
+
+impl<T: Sub<T, Output = T>> Sub for Quaternion<T> {
+  type Output = Quaternion<T>;
+
+  fn sub(self, rhs: Quaternion<T>) -> Quaternion<T> {
+    return Quaternion::new(
+      self.w - rhs.w,
+      self.x - rhs.x,
+      self.y - rhs.y,
+      self.z - rhs.z,
+    );
+  }
+}
+
+impl<T: Copy + Add<T, Output = T> + Sub<T, Output = T> + Mul<T, Output = T>> Mul for Quaternion<T> {
+  type Output = Quaternion<T>;
+
+  /// The Hamilton product.
+  fn mul(self, rhs: Quaternion<T>) -> Quaternion<T> {
+    let (a, b) = (self, rhs);
+    return Quaternion::new(
+      a.w * b.w - a.x * b.x - a.y * b.y - a.z * b.z,
+      a.w * b.x + a.x * b.w + a.y * b.z - a.z * b.y,
+      a.w * b.y - a.x * b.z + a.y * b.w + a.z * b.x,
+      a.w * b.z + a.x * b.y - a.y * b.x + a.z * b.w,
+    );
+  }
+}
+
+impl<
+    T: Copy
+      + Zero
+      + PartialOrd
+      + Add<T, Output = T>
+      + Sub<T, Output = T>
+      + Mul<T, Output = T>
+      + Div<T, Output = T>
+      + Neg<Output = T>,
+  > Div for Quaternion<T>
+{
+  type Output = Quaternion<T>;
+
+  /// Right division, `self * rhs.inverse()`. Panics if `rhs` is zero.
+  #[allow(clippy::suspicious_arithmetic_impl)]
+  fn div(self, rhs: Quaternion<T>) -> Quaternion<T> {
+    return self * rhs.inverse();
+  }
+}
+
+impl<T: Neg<Output = T>> Neg for Quaternion<T> {
+  type Output = Quaternion<T>;
+
+  fn neg(self) -> Quaternion<T> {
+    return Quaternion::new(-self.w, -self.x, -self.y, -self.z);
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::Quaternion;
+  use crate::core::arithmetic::{One, Zero};
+
+  static I: Quaternion<f64> = Quaternion {
+    w: 0.0,
+    x: 1.0,
+    y: 0.0,
+    z: 0.0,
+  };
+  static J: Quaternion<f64> = Quaternion {
+    w: 0.0,
+    x: 0.0,
+    y: 1.0,
+    z: 0.0,
+  };
+  static K: Quaternion<f64> = Quaternion {
+    w: 0.0,
+    x: 0.0,
+    y: 0.0,
+    z: 1.0,
+  };
+
+  fn test_values() -> Vec<Quaternion<f64>> {
+    return vec![
+      Quaternion::zero(),
+      Quaternion::one(),
+      I,
+      Quaternion::new(1.0, -2.0, 0.5, 4.0),
+    ];
+  }
+
+  #[test]
+  fn test_zero_is_additive_identity() {
+    for q in test_values() {
+      assert_eq!(q + Quaternion::zero(), q);
+      assert_eq!(q - Quaternion::zero(), q);
+      assert_eq!(Quaternion::zero() - q, -q);
+      assert_eq!(q * Quaternion::zero(), Quaternion::zero());
+    }
+  }
+
+  #[test]
+  fn test_one_is_multiplicative_identity() {
+    for q in test_values() {
+      assert_eq!(q * Quaternion::one(), q);
+      assert_eq!(Quaternion::one() * q, q);
+      assert_eq!(q / Quaternion::one(), q);
+    }
+  }
+
+  #[test]
+  fn test_hamilton_product_of_units() {
+    let minus_one = -Quaternion::<f64>::one();
+    assert_eq!(I * I, minus_one);
+    assert_eq!(J * J, minus_one);
+    assert_eq!(K * K, minus_one);
+    assert_eq!(I * J * K, minus_one);
+    assert_eq!(I * J, K);
+    assert_eq!(J * I, -K);
+    assert_eq!(J * K, I);
+    assert_eq!(K * J, -I);
+  }
+
+  #[test]
+  fn test_inverse_is_two_sided() {
+    let q = Quaternion::new(1.0, 1.0, -1.0, 1.0);
+    assert_eq!(q.norm_squared(), 4.0);
+    assert_eq!(q * q.inverse(), Quaternion::one());
+    assert_eq!(q.inverse() * q, Quaternion::one());
+    assert_eq!(q / q, Quaternion::one());
+  }
+
+  #[test]
+  fn test_inverse_of_extreme_magnitudes() {
+    for &scale in &[1e200, 1e-170, 1e300, f64::MIN_POSITIVE] {
+      let q = Quaternion::real(scale);
+      assert_eq!(q / q, Quaternion::one(), "{:?}", q);
+      assert_eq!(q.inverse() * q, Quaternion::one(), "{:?}", q);
+    }
+    let q = Quaternion::new(1e-170, 1e-170, -1e-170, 1e-170);
+    assert_eq!(q * q.inverse(), Quaternion::one());
+    assert_eq!(q.inverse().w, 2.5e169);
+  }
+
+  #[test]
+  #[should_panic(expected = "zero quaternion")]
+  fn test_inverse_of_zero_panics() {
+    let _ = Quaternion::<f64>::one() / Quaternion::zero();
+  }
+
+  #[test]
+  fn test_division_is_right_division() {
+    // i / j = i * (-j) = -k, whereas j^-1 * i = -j * i = k.
+    assert_eq!(I / J, -K);
+    assert_eq!(J.inverse() * I, K);
+  }
+}